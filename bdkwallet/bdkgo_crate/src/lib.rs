@@ -13,9 +13,14 @@ use bdk_wallet::{
     bitcoin::{
         self, consensus::{Decodable, Encodable}, hashes::Hash, network::ParseNetworkError, Address, BlockHash, Network, Transaction
     },
-    rusqlite::Connection, template::{Bip86, DescriptorTemplate}, KeychainKind, SignOptions
+    rusqlite::{Connection, OptionalExtension}, template::{Bip86, DescriptorTemplate}, KeychainKind, SignOptions
 };
+use argon2::Argon2;
 use bincode::Options;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
 use rand::RngCore;
 
 uniffi::include_scaffolding!("bdkgo");
@@ -23,6 +28,11 @@ uniffi::include_scaffolding!("bdkgo");
 const DB_MAGIC: &str = "utreexod.bdk.345e94cf";
 const DB_MAGIC_LEN: usize = DB_MAGIC.len();
 const ENTROPY_LEN: usize = 16; // 12 words
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24; // XChaCha20-Poly1305
+/// Deepest reorg we'll rewind for in one call to `rollback_to_height`, so a buggy or
+/// malicious peer can't force a full rescan by claiming an arbitrarily old fork point.
+const MAX_REORG: u32 = 100;
 
 type PBdkWallet = bdk_wallet::PersistedWallet<Connection>;
 
@@ -40,6 +50,8 @@ pub enum CreateNewError {
     Database(bdk_chain::rusqlite::Error),
     #[error("failed to init wallet: {0}")]
     Wallet(bdk_wallet::CreateWithPersistError<bdk_chain::rusqlite::Error>),
+    #[error("descriptor contains private key material; watch-only descriptors must be public-only")]
+    DescriptorHasPrivateKeys,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -52,6 +64,10 @@ pub enum LoadError {
     ParseHeader(bincode::Error),
     #[error("wallet header version unsupported")]
     HeaderVersion,
+    #[error("incorrect passphrase")]
+    BadPassphrase,
+    #[error("wallet header entropy is the wrong length; header is corrupt")]
+    CorruptEntropy,
     #[error("failed to init wallet: {0}")]
     Wallet(bdk_wallet::LoadWithPersistError<bdk_chain::rusqlite::Error>),
 }
@@ -62,6 +78,20 @@ pub enum DatabaseError {
     Write(bdk_chain::rusqlite::Error),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum WatchOnlyError {
+    #[error("wallet is watch-only; no private keys available")]
+    WatchOnly,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LabelError {
+    #[error("failed to read/write label: {0}")]
+    Database(bdk_chain::rusqlite::Error),
+    #[error("failed to parse BIP329 label record: {0}")]
+    ParseRecord(serde_json::Error),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApplyBlockError {
     #[error("failed to decode block: {0}")]
@@ -70,6 +100,10 @@ pub enum ApplyBlockError {
     CannotConnect(bdk_chain::local_chain::CannotConnectError),
     #[error("failed to write block to db: {0}")]
     Database(bdk_chain::rusqlite::Error),
+    #[error("cannot roll back {depth} blocks below tip; exceeds MAX_REORG of {max}")]
+    ReorgTooDeep { depth: u32, max: u32 },
+    #[error("no checkpoint exists at height {0}; cannot roll back to it exactly")]
+    NoCheckpointAtHeight(u32),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -86,17 +120,66 @@ pub enum CreateTxError {
     CreateTx(bdk_wallet::error::CreateTxError<>),
     #[error("failed to sign tx: {0}")]
     SignTx(bdk_wallet::signer::SignerError),
+    #[error("failed to finalize psbt: {0}")]
+    Finalize(FinalizePsbtError),
+    #[error("transaction not found in wallet: {0:?}")]
+    TxNotFound(Vec<u8>),
+    #[error("transaction is not replaceable: {0:?}")]
+    NotReplaceable(Vec<u8>),
+    #[error("failed to prepare fee bump: {0}")]
+    FeeBump(bdk_wallet::error::BuildFeeBumpError),
+    #[error("invalid outpoint: {0}")]
+    InvalidOutpoint(bdk_wallet::bitcoin::hashes::FromSliceError),
+    #[error("failed to add utxo to transaction: {0}")]
+    AddUtxo(bdk_wallet::wallet::tx_builder::AddUtxoError),
+    #[error("insufficient funds: needed {needed} sat, available {available} sat")]
+    InsufficientFunds { needed: u64, available: u64 },
+    #[error("wallet is watch-only; no private keys available to sign")]
+    WatchOnly,
+    #[error("cannot combine drain_to with explicit recipients")]
+    DrainWithRecipients,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FinalizePsbtError {
+    #[error("failed to parse psbt: {0}")]
+    ParsePsbt(bdk_wallet::bitcoin::psbt::Error),
+    #[error("failed to finalize psbt: {0}")]
+    SignTx(bdk_wallet::signer::SignerError),
+    #[error("psbt is missing signatures and cannot be finalized")]
+    NotFinalized,
+    #[error("failed to extract tx from psbt: {0}")]
+    ExtractTx(bdk_wallet::bitcoin::psbt::ExtractTxError),
 }
 pub struct AddressInfo {
     pub index: u32,
     pub address: String,
+    pub label: Option<String>,
 }
 
+/// On-disk shape of [`WalletHeader`]. `entropy` holds the plaintext seed bytes when
+/// `encrypted` is false (the sentinel preserving the original unencrypted behavior), or
+/// `salt || nonce || ciphertext` (see [`WalletHeader::encrypt_entropy`]) when true. `version`
+/// and `network` always stay in the clear so `decode` can detect the format before a
+/// passphrase is even known.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct EncodedHeader {
+    version: [u8; DB_MAGIC_LEN],
+    encrypted: bool,
+    entropy: Vec<u8>,
+    network: Network,
+    watch_only: bool,
+    external_descriptor: Option<String>,
+    internal_descriptor: Option<String>,
+}
+
 pub struct WalletHeader {
     pub version: [u8; DB_MAGIC_LEN],
     pub entropy: [u8; ENTROPY_LEN],
     pub network: Network,
+    pub watch_only: bool,
+    pub external_descriptor: Option<String>,
+    pub internal_descriptor: Option<String>,
 }
 
 impl WalletHeader {
@@ -109,19 +192,100 @@ impl WalletHeader {
             version,
             entropy,
             network,
+            watch_only: false,
+            external_descriptor: None,
+            internal_descriptor: None,
         }
     }
 
-    pub fn encode(&mut self) -> Vec<u8> {
+    /// Builds a header for a monitoring-only wallet: it carries public descriptors instead
+    /// of seed entropy, so `descriptor` and `mnemonic_words` never touch private keys.
+    pub fn new_watch_only(
+        network: Network,
+        external_descriptor: String,
+        internal_descriptor: String,
+    ) -> Self {
+        let mut version = [0_u8; DB_MAGIC_LEN];
+        version.copy_from_slice(DB_MAGIC.as_bytes());
+        Self {
+            version,
+            entropy: [0_u8; ENTROPY_LEN],
+            network,
+            watch_only: true,
+            external_descriptor: Some(external_descriptor),
+            internal_descriptor: Some(internal_descriptor),
+        }
+    }
+
+    /// Derives a 32-byte key from `passphrase` and `salt` with Argon2id.
+    fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+        let mut key = [0_u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .expect("argon2 must derive a key");
+        key
+    }
+
+    /// Encrypts `entropy` with a freshly generated salt and nonce, returning
+    /// `salt || nonce || ciphertext`.
+    fn encrypt_entropy(entropy: &[u8], passphrase: &str) -> Vec<u8> {
+        let mut salt = [0_u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = Self::derive_key(passphrase, &salt);
+
+        let mut nonce_bytes = [0_u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let cipher = XChaCha20Poly1305::new((&key).into());
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce_bytes), entropy)
+            .expect("encryption must succeed");
+
+        salt.into_iter().chain(nonce_bytes).chain(ciphertext).collect()
+    }
+
+    /// Reverses [`Self::encrypt_entropy`], returning [`LoadError::BadPassphrase`] on any
+    /// AEAD tag failure (wrong passphrase or corrupt header).
+    fn decrypt_entropy(blob: &[u8], passphrase: &str) -> Result<Vec<u8>, LoadError> {
+        if blob.len() < SALT_LEN + NONCE_LEN {
+            return Err(LoadError::BadPassphrase);
+        }
+        let (salt, rest) = blob.split_at(SALT_LEN);
+        let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+        let key = Self::derive_key(passphrase, salt);
+        let cipher = XChaCha20Poly1305::new((&key).into());
+        cipher
+            .decrypt(XNonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|_| LoadError::BadPassphrase)
+    }
+
+    /// Encodes the header, encrypting `entropy` with `passphrase` via Argon2id +
+    /// XChaCha20-Poly1305. An empty passphrase stores `entropy` in the clear with the
+    /// `encrypted` flag unset. Either way the bytes are an [`EncodedHeader`], not the older
+    /// fixed-size header layout.
+    pub fn encode(&mut self, passphrase: &str) -> Vec<u8> {
         self.version.copy_from_slice(DB_MAGIC.as_bytes());
+        let (entropy, encrypted) = if passphrase.is_empty() {
+            (self.entropy.to_vec(), false)
+        } else {
+            (Self::encrypt_entropy(&self.entropy, passphrase), true)
+        };
+        let wire = EncodedHeader {
+            version: self.version,
+            encrypted,
+            entropy,
+            network: self.network,
+            watch_only: self.watch_only,
+            external_descriptor: self.external_descriptor.clone(),
+            internal_descriptor: self.internal_descriptor.clone(),
+        };
         let b = bincode_config()
-            .serialize(&self)
+            .serialize(&wire)
             .expect("bincode must serialize");
         let l = (b.len() as u32).to_le_bytes();
         l.into_iter().chain(b).collect::<Vec<u8>>()
     }
 
-    pub fn decode<R: Read>(mut r: R) -> Result<Self, LoadError> {
+    pub fn decode<R: Read>(mut r: R, passphrase: &str) -> Result<Self, LoadError> {
         let mut l_buf = [0_u8; 4];
         r.read_exact(&mut l_buf)
             .map_err(|err| LoadError::ReadHeader(err))?;
@@ -130,17 +294,48 @@ impl WalletHeader {
         r.read_exact(&mut b)
             .map_err(|err| LoadError::ReadHeader(err))?;
 
-        let header = bincode_config()
-            .deserialize::<WalletHeader>(&b)
+        let wire = bincode_config()
+            .deserialize::<EncodedHeader>(&b)
             .map_err(LoadError::ParseHeader)?;
-        if header.version != DB_MAGIC.as_bytes() {
+        if wire.version != DB_MAGIC.as_bytes() {
             return Err(LoadError::HeaderVersion);
         }
 
-        Ok(header)
+        let entropy_bytes = if wire.encrypted {
+            Self::decrypt_entropy(&wire.entropy, passphrase)?
+        } else {
+            wire.entropy
+        };
+        let entropy: [u8; ENTROPY_LEN] = entropy_bytes
+            .try_into()
+            .map_err(|_| LoadError::CorruptEntropy)?;
+
+        Ok(Self {
+            version: wire.version,
+            entropy,
+            network: wire.network,
+            watch_only: wire.watch_only,
+            external_descriptor: wire.external_descriptor,
+            internal_descriptor: wire.internal_descriptor,
+        })
     }
 
+    /// Returns the descriptor for `keychain`: the stored public descriptor for a watch-only
+    /// header, or one derived from seed entropy otherwise.
     pub fn descriptor(&self, keychain: KeychainKind) -> String {
+        if self.watch_only {
+            return match keychain {
+                KeychainKind::External => self
+                    .external_descriptor
+                    .clone()
+                    .expect("watch-only header always carries an external descriptor"),
+                KeychainKind::Internal => self
+                    .internal_descriptor
+                    .clone()
+                    .expect("watch-only header always carries an internal descriptor"),
+            };
+        }
+
         let xpriv: Xpriv = Xpriv::new_master(self.network, &self.entropy).unwrap();
         let (descriptor, key_map, _) = Bip86(xpriv, keychain)
             .build(self.network)
@@ -172,15 +367,11 @@ impl Wallet {
         unsafe { Arc::increment_strong_count(Arc::into_raw(Arc::clone(self))) }
     }
 
-    pub fn create_new(
-        db_path: String,
-        network: String,
-    ) -> Result<Self, CreateNewError> {
-        let network = Network::from_str(&network).map_err(CreateNewError::ParseNetwork)?;
-
-        let mut header = WalletHeader::new(network);
-        let header_bytes = header.encode();
-        let mut conn = match Connection::open(&db_path) {
+    /// Opens `db_path`, creates the `header` table and writes `header_bytes` into it.
+    /// Shared by `create_new` and `create_watch_only`, which differ only in the header
+    /// contents and the descriptors used to build the wallet.
+    fn create_header_db(db_path: &str, header_bytes: Vec<u8>) -> Result<Connection, CreateNewError> {
+        let conn = match Connection::open(db_path) {
             Ok(c) => c,
             Err(err) => {
                 let _ = std::fs::remove_file(db_path);
@@ -188,7 +379,7 @@ impl Wallet {
             }
         };
 
-         match conn.execute(
+        match conn.execute(
             "CREATE TABLE IF NOT EXISTS header (
                 data BLOB NOT NULL
             )",
@@ -212,6 +403,99 @@ impl Wallet {
             }
         };
 
+        if let Err(err) = Self::ensure_labels_table(&conn) {
+            let _ = std::fs::remove_file(db_path);
+            return Err(CreateNewError::Database(err));
+        }
+
+        Ok(conn)
+    }
+
+    /// Creates the `labels` table if it doesn't already exist, so label lookups work on
+    /// both freshly created wallets and ones persisted before this table was introduced.
+    fn ensure_labels_table(conn: &Connection) -> Result<(), bdk_chain::rusqlite::Error> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS labels (
+                ref_kind TEXT NOT NULL,
+                ref_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                PRIMARY KEY (ref_kind, ref_id)
+            )",
+            (),
+        )?;
+        Ok(())
+    }
+
+    /// Looks up the label stored for `(ref_kind, ref_id)`, if any.
+    fn label_for(conn: &Connection, ref_kind: &str, ref_id: &str) -> Result<Option<String>, bdk_chain::rusqlite::Error> {
+        conn.query_row(
+            "SELECT label FROM labels WHERE ref_kind = ?1 AND ref_id = ?2",
+            (ref_kind, ref_id),
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    pub fn create_new(
+        db_path: String,
+        network: String,
+        passphrase: String,
+    ) -> Result<Self, CreateNewError> {
+        let network = Network::from_str(&network).map_err(CreateNewError::ParseNetwork)?;
+
+        let mut header = WalletHeader::new(network);
+        let header_bytes = header.encode(&passphrase);
+        let mut conn = Self::create_header_db(&db_path, header_bytes)?;
+
+        let wallet = match bdk_wallet::Wallet::create(
+            header.descriptor(KeychainKind::External),
+            header.descriptor(KeychainKind::Internal))
+            .network(network)
+            .create_wallet(&mut conn)
+        {
+            Ok(w) => w,
+            Err(err) => {
+                let _ = std::fs::remove_file(db_path);
+                return Err(CreateNewError::Wallet(err));
+            }
+        };
+
+        let inner = Mutex::new(wallet);
+        let header = Mutex::new(header);
+        let conn = Mutex::new(conn);
+        Ok(Self { inner, conn, header })
+    }
+
+    /// Rejects `descriptor` if it carries any private key material. A descriptor is
+    /// public-only iff it parses as `Descriptor<DescriptorPublicKey>`; any embedded xprv,
+    /// WIF, or other secret key makes that parse fail.
+    fn ensure_no_private_keys(descriptor: &str) -> Result<(), CreateNewError> {
+        bdk_wallet::miniscript::Descriptor::<bdk_wallet::keys::DescriptorPublicKey>::from_str(
+            descriptor,
+        )
+        .map_err(|_| CreateNewError::DescriptorHasPrivateKeys)?;
+        Ok(())
+    }
+
+    /// Creates a monitoring-only wallet from exported public descriptors: no seed entropy
+    /// is generated or stored, so `mnemonic_words`, `create_tx` and `bump_fee` return
+    /// `WatchOnly` errors, while balance/utxo/tx tracking and `create_psbt` (for offline
+    /// signing) keep working.
+    pub fn create_watch_only(
+        db_path: String,
+        network: String,
+        external_descriptor: String,
+        internal_descriptor: String,
+    ) -> Result<Self, CreateNewError> {
+        let network = Network::from_str(&network).map_err(CreateNewError::ParseNetwork)?;
+        Self::ensure_no_private_keys(&external_descriptor)?;
+        Self::ensure_no_private_keys(&internal_descriptor)?;
+
+        let mut header =
+            WalletHeader::new_watch_only(network, external_descriptor, internal_descriptor);
+        let header_bytes = header.encode("");
+        let mut conn = Self::create_header_db(&db_path, header_bytes)?;
+
         let wallet = match bdk_wallet::Wallet::create(
             header.descriptor(KeychainKind::External),
             header.descriptor(KeychainKind::Internal))
@@ -231,27 +515,31 @@ impl Wallet {
         Ok(Self { inner, conn, header })
     }
 
-    pub fn load(db_path: String, genesis_hash: Vec<u8>) -> Result<Self, LoadError> {
+    pub fn load(db_path: String, genesis_hash: Vec<u8>, passphrase: String) -> Result<Self, LoadError> {
         let mut conn = bdk_wallet::rusqlite::Connection::open(&db_path).map_err(LoadError::Database)?;
+        Self::ensure_labels_table(&conn).map_err(LoadError::Database)?;
 
         let header = {
             let mut stmt = conn.prepare("SELECT data FROM header LIMIT 1").map_err(LoadError::Database)?;
             let result: Vec<u8> = stmt.query_row([], |row| row.get(0)).map_err(LoadError::Database)?;
             let bytes: &[u8] = &result;
-            WalletHeader::decode(bytes)?
+            WalletHeader::decode(bytes, &passphrase)?
         };
 
-        let wallet = match bdk_wallet::Wallet::load()
-            // check loaded descriptors matches these values and extract private keys
+        let mut load_params = bdk_wallet::Wallet::load()
+            // check loaded descriptors matches these values
             .descriptor(KeychainKind::External, Some(header.descriptor(KeychainKind::External)))
             .descriptor(KeychainKind::Internal, Some(header.descriptor(KeychainKind::Internal)))
-            .extract_keys()
             // ensure loaded wallet's genesis hash matches this value
             .check_genesis_hash(BlockHash::from_slice(&genesis_hash).unwrap())
             // set a lookahead for our indexer
-            .lookahead(101)
-            .load_wallet(&mut conn)
-        {
+            .lookahead(101);
+        if !header.watch_only {
+            // extract private keys, which a watch-only header never has
+            load_params = load_params.extract_keys();
+        }
+
+        let wallet = match load_params.load_wallet(&mut conn) {
             Ok(w) => w.unwrap(),
             Err(err) => {
                 return Err(LoadError::Wallet(err));
@@ -268,7 +556,10 @@ impl Wallet {
         self.increment_reference_counter();
         let mut wallet = self.inner.lock().unwrap();
         let bdk_addr = wallet.next_unused_address(bdk_wallet::KeychainKind::External);
-        Ok(AddressInfo { index: bdk_addr.index, address: bdk_addr.address.to_string() })
+        let conn = self.conn.lock().unwrap();
+        let label = Self::label_for(&conn, "derivation", &format!("external:{}", bdk_addr.index))
+            .map_err(DatabaseError::Write)?;
+        Ok(AddressInfo { index: bdk_addr.index, address: bdk_addr.address.to_string(), label })
     }
 
     pub fn fresh_address(self: Arc<Self>) -> Result<AddressInfo, DatabaseError> {
@@ -277,14 +568,19 @@ impl Wallet {
         let bdk_addr = wallet.reveal_next_address(bdk_wallet::KeychainKind::External);
         let mut c = self.conn.lock().unwrap();
         wallet.persist(&mut c).unwrap();
-        Ok(AddressInfo { index: bdk_addr.index, address: bdk_addr.address.to_string() })
+        let label = Self::label_for(&c, "derivation", &format!("external:{}", bdk_addr.index))
+            .map_err(DatabaseError::Write)?;
+        Ok(AddressInfo { index: bdk_addr.index, address: bdk_addr.address.to_string(), label })
     }
 
     pub fn peek_address(self: Arc<Self>, index: u32) -> Result<AddressInfo, DatabaseError> {
         self.increment_reference_counter();
         let wallet = self.inner.lock().unwrap();
         let bdk_addr = wallet.peek_address(bdk_wallet::KeychainKind::External, index);
-        Ok(AddressInfo { index: bdk_addr.index, address: bdk_addr.address.to_string() })
+        let conn = self.conn.lock().unwrap();
+        let label = Self::label_for(&conn, "derivation", &format!("external:{}", bdk_addr.index))
+            .map_err(DatabaseError::Write)?;
+        Ok(AddressInfo { index: bdk_addr.index, address: bdk_addr.address.to_string(), label })
     }
 
     pub fn balance(self: Arc<Self>) -> Balance {
@@ -322,6 +618,11 @@ impl Wallet {
             .collect()
     }
 
+    /// Connects `block_bytes` at `height` to the wallet's chain.
+    ///
+    /// On `ApplyBlockError::CannotConnect` the caller is on a fork: call
+    /// [`Wallet::rollback_to_height`] with the common ancestor height to rewind the wallet's
+    /// chain before re-applying blocks from that point.
     pub fn apply_block(
         self: Arc<Self>,
         height: u32,
@@ -359,6 +660,72 @@ impl Wallet {
         Ok(res)
     }
 
+    /// Finds the checkpoint at exactly `height` in `tip`'s chain and checks that rolling back
+    /// to it doesn't exceed `MAX_REORG`. Pulled out of `rollback_to_height` so the bound can
+    /// be unit-tested against a synthetic chain without a full wallet/db.
+    fn resolve_rollback_target(
+        tip: &bdk_chain::CheckPoint,
+        height: u32,
+    ) -> Result<bdk_chain::CheckPoint, ApplyBlockError> {
+        let fork_point = tip
+            .iter()
+            .find(|cp| cp.height() <= height)
+            .expect("local chain always has a checkpoint at or below its own tip");
+        if fork_point.height() != height {
+            return Err(ApplyBlockError::NoCheckpointAtHeight(height));
+        }
+
+        let depth = tip.height().saturating_sub(fork_point.height());
+        if depth > MAX_REORG {
+            return Err(ApplyBlockError::ReorgTooDeep {
+                depth,
+                max: MAX_REORG,
+            });
+        }
+
+        Ok(fork_point)
+    }
+
+    /// Rewinds the wallet's chain to `height`, disconnecting every checkpoint above it and
+    /// moving any confirmed txs anchored there back to unconfirmed. Use this to rewind to a
+    /// fork point after `apply_block` reports `CannotConnect`, before re-applying the new
+    /// chain's blocks. Bounded by `MAX_REORG` so a buggy or malicious peer can't force a full
+    /// rescan by asking for an arbitrarily deep rollback.
+    pub fn rollback_to_height(
+        self: Arc<Self>,
+        height: u32,
+    ) -> Result<ApplyResult, ApplyBlockError> {
+        self.increment_reference_counter();
+        let mut wallet = self.inner.lock().unwrap();
+
+        let tip = wallet.latest_checkpoint();
+        let fork_point = Self::resolve_rollback_target(&tip, height)?;
+
+        // `apply_update` only stages a new chain tip, not tx_graph changes, so
+        // `ApplyResult::new` (which reads staged tx_graph inserts) would see nothing here.
+        // Capture the txids that are about to lose their confirmation before rolling back.
+        let relevant_txids = wallet
+            .transactions()
+            .filter(|ctx| {
+                ctx.chain_position
+                    .confirmation_height_upper_bound()
+                    .is_some_and(|conf_height| conf_height > height)
+            })
+            .map(|ctx| ctx.tx_node.txid.to_byte_array().to_vec())
+            .collect::<Vec<_>>();
+
+        wallet
+            .apply_update(bdk_wallet::Update {
+                chain: Some(fork_point),
+                ..Default::default()
+            })
+            .map_err(ApplyBlockError::CannotConnect)?;
+
+        let mut c = self.conn.lock().unwrap();
+        wallet.persist(&mut c).map_err(ApplyBlockError::Database)?;
+
+        Ok(ApplyResult { relevant_txids })
+    }
 
     pub fn apply_mempool(
         self: Arc<Self>,
@@ -385,10 +752,15 @@ impl Wallet {
         Ok(res)
     }
 
-    pub fn create_tx(
+    /// Builds an unsigned PSBT paying `recipients` at `feerate`, without touching any
+    /// private key material. Use this to hand the PSBT to an external/hardware signer,
+    /// then pass the signed result to [`Wallet::finalize_and_extract`]. When `enable_rbf`
+    /// is set the tx signals BIP125 replaceability, which [`Wallet::bump_fee`] later relies on.
+    pub fn create_psbt(
         self: Arc<Self>,
         feerate: u64,
         recipients: Vec<Recipient>,
+        enable_rbf: bool,
     ) -> Result<Vec<u8>, CreateTxError> {
         self.increment_reference_counter();
         let mut wallet = self.inner.lock().unwrap();
@@ -407,8 +779,73 @@ impl Wallet {
         let mut txb = wallet.build_tx();
         txb.set_recipients(recipients);
         txb.fee_rate(FeeRate::from_sat_per_vb(feerate).unwrap());
-        let mut psbt = txb.finish().map_err(CreateTxError::CreateTx)?;
+        if enable_rbf {
+            txb.enable_rbf();
+        }
+        let psbt = txb.finish().map_err(CreateTxError::CreateTx)?;
+
+        Ok(psbt.serialize())
+    }
+
+    /// Finalizes a (possibly externally signed) PSBT and extracts the raw consensus-encoded
+    /// transaction, ready for broadcast.
+    pub fn finalize_and_extract(
+        self: Arc<Self>,
+        psbt_bytes: Vec<u8>,
+    ) -> Result<Vec<u8>, FinalizePsbtError> {
+        self.increment_reference_counter();
+        let wallet = self.inner.lock().unwrap();
+
+        let mut psbt =
+            bitcoin::Psbt::deserialize(&psbt_bytes).map_err(FinalizePsbtError::ParsePsbt)?;
+
+        let is_finalized = wallet
+            .finalize_psbt(&mut psbt, SignOptions::default())
+            .map_err(FinalizePsbtError::SignTx)?;
+        if !is_finalized {
+            return Err(FinalizePsbtError::NotFinalized);
+        }
+
+        let mut raw_bytes = Vec::<u8>::new();
+        psbt.extract_tx()
+            .map_err(FinalizePsbtError::ExtractTx)?
+            .consensus_encode(&mut raw_bytes)
+            .expect("must encode tx");
+        Ok(raw_bytes)
+    }
+
+    /// Convenience wrapper that builds, signs and extracts a tx in one call, for callers
+    /// that keep keys on the same host as this wallet.
+    pub fn create_tx(
+        self: Arc<Self>,
+        feerate: u64,
+        recipients: Vec<Recipient>,
+        enable_rbf: bool,
+    ) -> Result<Vec<u8>, CreateTxError> {
+        self.increment_reference_counter();
+        if self.header.lock().unwrap().watch_only {
+            return Err(CreateTxError::WatchOnly);
+        }
+        let psbt_bytes = Arc::clone(&self).create_psbt(feerate, recipients, enable_rbf)?;
+        let mut psbt =
+            bitcoin::Psbt::deserialize(&psbt_bytes).expect("psbt we just serialized must parse");
+        self.inner
+            .lock()
+            .unwrap()
+            .sign(&mut psbt, SignOptions { try_finalize: false, ..Default::default() })
+            .map_err(CreateTxError::SignTx)?;
+        Arc::clone(&self)
+            .finalize_and_extract(psbt.serialize())
+            .map_err(CreateTxError::Finalize)
+    }
 
+    /// Signs `psbt` with the wallet's own keys and extracts the raw consensus-encoded tx.
+    /// Shared by call sites that build and sign in one step (as opposed to
+    /// [`Wallet::finalize_and_extract`], which finalizes a PSBT signed elsewhere).
+    fn sign_and_extract(
+        wallet: &mut PBdkWallet,
+        mut psbt: bitcoin::Psbt,
+    ) -> Result<Vec<u8>, CreateTxError> {
         let is_finalized = wallet
             .sign(&mut psbt, SignOptions::default())
             .map_err(CreateTxError::SignTx)?;
@@ -422,14 +859,135 @@ impl Wallet {
         Ok(raw_bytes)
     }
 
-    pub fn mnemonic_words(self: Arc<Self>) -> Vec<String> {
+    /// Replaces the tx identified by `txid` with one paying `new_feerate`, signalling BIP125
+    /// replacement of the original. Only works if the original was created with
+    /// `enable_rbf = true`.
+    pub fn bump_fee(
+        self: Arc<Self>,
+        txid: Vec<u8>,
+        new_feerate: u64,
+    ) -> Result<Vec<u8>, CreateTxError> {
         self.increment_reference_counter();
-        self.header.lock().unwrap().mnemonic_words()
+        if self.header.lock().unwrap().watch_only {
+            return Err(CreateTxError::WatchOnly);
+        }
+        let mut wallet = self.inner.lock().unwrap();
+
+        let parsed_txid = bitcoin::Txid::from_slice(&txid)
+            .map_err(|_| CreateTxError::TxNotFound(txid.clone()))?;
+
+        let mut txb = wallet
+            .build_fee_bump(parsed_txid)
+            .map_err(|err| match err {
+                bdk_wallet::error::BuildFeeBumpError::TransactionNotFound(txid) => {
+                    CreateTxError::TxNotFound(txid.to_byte_array().to_vec())
+                }
+                bdk_wallet::error::BuildFeeBumpError::IrreplaceableTransaction(txid) => {
+                    CreateTxError::NotReplaceable(txid.to_byte_array().to_vec())
+                }
+                other => CreateTxError::FeeBump(other),
+            })?;
+        txb.fee_rate(FeeRate::from_sat_per_vb(new_feerate).unwrap());
+        let psbt = txb.finish().map_err(CreateTxError::CreateTx)?;
+
+        Self::sign_and_extract(&mut wallet, psbt)
     }
 
-    pub fn transactions(self: Arc<Self>) -> Vec<TxInfo> {
+    /// Like [`Wallet::create_tx`] but with manual coin control: `must_use_utxos` are
+    /// force-included, `must_avoid_utxos` are excluded from automatic selection, and
+    /// `drain_to`, when set, sweeps the entire confirmed balance (minus fee) to one address
+    /// instead of paying `recipients` explicit amounts.
+    pub fn create_tx_advanced(
+        self: Arc<Self>,
+        feerate: u64,
+        recipients: Vec<Recipient>,
+        enable_rbf: bool,
+        must_use_utxos: Vec<OutPoint>,
+        must_avoid_utxos: Vec<OutPoint>,
+        drain_to: Option<String>,
+    ) -> Result<Vec<u8>, CreateTxError> {
+        self.increment_reference_counter();
+        if self.header.lock().unwrap().watch_only {
+            return Err(CreateTxError::WatchOnly);
+        }
+        let mut wallet = self.inner.lock().unwrap();
+
+        let must_use_utxos = must_use_utxos
+            .into_iter()
+            .map(bitcoin::OutPoint::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(CreateTxError::InvalidOutpoint)?;
+        let must_avoid_utxos = must_avoid_utxos
+            .into_iter()
+            .map(bitcoin::OutPoint::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(CreateTxError::InvalidOutpoint)?;
+
+        let network = wallet.network();
+        let mut txb = wallet.build_tx();
+
+        if let Some(drain_to) = drain_to {
+            if !recipients.is_empty() {
+                return Err(CreateTxError::DrainWithRecipients);
+            }
+            let addr = Address::from_str(&drain_to)
+                .map_err(CreateTxError::InvalidAddress)?
+                .require_network(network)
+                .map_err(CreateTxError::InvalidAddress)?;
+            txb.drain_wallet();
+            txb.drain_to(addr.script_pubkey());
+        } else {
+            let recipients = recipients
+                .into_iter()
+                .map(|r| -> Result<_, _> {
+                    let addr = Address::from_str(&r.address)
+                        .map_err(CreateTxError::InvalidAddress)?
+                        .require_network(network)
+                        .map_err(CreateTxError::InvalidAddress)?;
+                    Ok((addr.script_pubkey().into(), bdk_chain::bitcoin::Amount::from_sat(r.amount)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            txb.set_recipients(recipients);
+        }
+
+        for outpoint in must_use_utxos {
+            txb.add_utxo(outpoint).map_err(CreateTxError::AddUtxo)?;
+        }
+        if !must_avoid_utxos.is_empty() {
+            txb.unspendable(must_avoid_utxos);
+        }
+
+        txb.fee_rate(FeeRate::from_sat_per_vb(feerate).unwrap());
+        if enable_rbf {
+            txb.enable_rbf();
+        }
+
+        let psbt = txb.finish().map_err(|err| match err {
+            bdk_wallet::error::CreateTxError::CoinSelection(
+                bdk_wallet::coin_selection::InsufficientFunds { needed, available },
+            ) => CreateTxError::InsufficientFunds {
+                needed: needed.to_sat(),
+                available: available.to_sat(),
+            },
+            other => CreateTxError::CreateTx(other),
+        })?;
+
+        Self::sign_and_extract(&mut wallet, psbt)
+    }
+
+    pub fn mnemonic_words(self: Arc<Self>) -> Result<Vec<String>, WatchOnlyError> {
+        self.increment_reference_counter();
+        let header = self.header.lock().unwrap();
+        if header.watch_only {
+            return Err(WatchOnlyError::WatchOnly);
+        }
+        Ok(header.mnemonic_words())
+    }
+
+    pub fn transactions(self: Arc<Self>) -> Result<Vec<TxInfo>, DatabaseError> {
         self.increment_reference_counter();
         let wallet = self.inner.lock().unwrap();
+        let conn = self.conn.lock().unwrap();
         let height = wallet.latest_checkpoint().height();
         let mut txs = wallet
             .transactions()
@@ -445,45 +1003,166 @@ impl Wallet {
                     .chain_position
                     .confirmation_height_upper_bound()
                     .map_or(0, |conf_height| (1 + height).saturating_sub(conf_height));
-                TxInfo {
+                let label =
+                    Self::label_for(&conn, "tx", &ctx.tx_node.txid.to_string())?;
+                Ok(TxInfo {
                     txid: txid,
                     tx: tx,
                     spent: spent.to_sat(),
                     received: received.to_sat(),
                     confirmations: confirmations,
-                }
+                    label,
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, bdk_chain::rusqlite::Error>>()
+            .map_err(DatabaseError::Write)?;
         txs.sort_unstable_by_key(|tx| Reverse(tx.confirmations));
-        txs
+        Ok(txs)
     }
 
-    pub fn utxos(self: Arc<Self>) -> Vec<UtxoInfo> {
+    pub fn utxos(self: Arc<Self>) -> Result<Vec<UtxoInfo>, DatabaseError> {
         self.increment_reference_counter();
         let wallet = self.inner.lock().unwrap();
+        let conn = self.conn.lock().unwrap();
         let wallet_height = wallet.latest_checkpoint().height();
         let mut utxos = wallet
             .list_unspent()
-            .map(|utxo| UtxoInfo {
-                txid: utxo.outpoint.txid.to_byte_array().to_vec(),
-                vout: utxo.outpoint.vout,
-                amount: utxo.txout.value.to_sat(),
-                script_pubkey: utxo.txout.script_pubkey.to_bytes(),
-                is_change: utxo.keychain == KeychainKind::Internal,
-                derivation_index: utxo.derivation_index,
-                confirmations: match utxo.chain_position {
-                    bdk_chain::ChainPosition::Confirmed { anchor, .. } => {
-                        (1 + wallet_height).saturating_sub(anchor.confirmation_height_upper_bound())
-                    }
-                    bdk_chain::ChainPosition::Unconfirmed { .. } => 0,
-                },
+            .map(|utxo| {
+                let script_pubkey = utxo.txout.script_pubkey.to_bytes();
+                let label = Self::label_for(
+                    &conn,
+                    "output",
+                    &format!("{}:{}", utxo.outpoint.txid, utxo.outpoint.vout),
+                )?;
+                Ok(UtxoInfo {
+                    txid: utxo.outpoint.txid.to_byte_array().to_vec(),
+                    vout: utxo.outpoint.vout,
+                    amount: utxo.txout.value.to_sat(),
+                    script_pubkey,
+                    is_change: utxo.keychain == KeychainKind::Internal,
+                    derivation_index: utxo.derivation_index,
+                    confirmations: match utxo.chain_position {
+                        bdk_chain::ChainPosition::Confirmed { anchor, .. } => {
+                            (1 + wallet_height).saturating_sub(anchor.confirmation_height_upper_bound())
+                        }
+                        bdk_chain::ChainPosition::Unconfirmed { .. } => 0,
+                    },
+                    label,
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, bdk_chain::rusqlite::Error>>()
+            .map_err(DatabaseError::Write)?;
         utxos.sort_unstable_by_key(|utxo| Reverse(utxo.confirmations));
-        utxos
+        Ok(utxos)
+    }
+
+    /// Sets (or overwrites) the label for a reference. `ref_kind` is one of the BIP329
+    /// type strings this wallet tracks labels for — `"tx"` (`ref_id` the display-form
+    /// txid, matching [`Wallet::export_labels`]) or `"output"` (`ref_id` `"txid:vout"`) —
+    /// or the wallet-internal `"derivation"` kind (`ref_id` `"{external,internal}:{index}"`),
+    /// which isn't a BIP329 concept and is skipped by export/import.
+    pub fn set_label(
+        self: Arc<Self>,
+        ref_kind: String,
+        ref_id: String,
+        label: String,
+    ) -> Result<(), LabelError> {
+        self.increment_reference_counter();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO labels (ref_kind, ref_id, label) VALUES (?1, ?2, ?3)
+             ON CONFLICT(ref_kind, ref_id) DO UPDATE SET label = excluded.label",
+            (ref_kind, ref_id, label),
+        )
+        .map_err(LabelError::Database)?;
+        Ok(())
+    }
+
+    /// Returns the label stored for `(ref_kind, ref_id)`, if any. See [`Wallet::set_label`]
+    /// for the reference format.
+    pub fn get_label(
+        self: Arc<Self>,
+        ref_kind: String,
+        ref_id: String,
+    ) -> Result<Option<String>, LabelError> {
+        self.increment_reference_counter();
+        let conn = self.conn.lock().unwrap();
+        Self::label_for(&conn, &ref_kind, &ref_id).map_err(LabelError::Database)
+    }
+
+    /// Exports every BIP329-representable stored label as a BIP329 JSONL blob (one
+    /// `{"type","ref","label"}` record per line). Labels stored under the wallet-internal
+    /// `"derivation"` kind have no BIP329 equivalent and are not exported.
+    pub fn export_labels(self: Arc<Self>) -> Result<String, LabelError> {
+        self.increment_reference_counter();
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT ref_kind, ref_id, label FROM labels
+                 WHERE ref_kind IN ('tx', 'output') ORDER BY ref_kind, ref_id",
+            )
+            .map_err(LabelError::Database)?;
+        let records = stmt
+            .query_map([], |row| {
+                Ok(Bip329Record {
+                    kind: row.get(0)?,
+                    reference: row.get(1)?,
+                    label: row.get(2)?,
+                })
+            })
+            .map_err(LabelError::Database)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(LabelError::Database)?;
+
+        let mut out = String::new();
+        for record in records {
+            out.push_str(&serde_json::to_string(&record).expect("label record must serialize"));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Imports labels from a BIP329 JSONL blob, upserting each record whose `type` this
+    /// wallet tracks labels for (`"tx"`, `"output"`). Other BIP329 types (`"address"`,
+    /// `"input"`, `"pubkey"`, `"xpub"`) aren't backed by a queryable label lookup here, so
+    /// they're skipped rather than stored and silently lost. Returns the number of records
+    /// actually imported.
+    pub fn import_labels(self: Arc<Self>, jsonl: String) -> Result<u32, LabelError> {
+        self.increment_reference_counter();
+        let conn = self.conn.lock().unwrap();
+        let mut count = 0_u32;
+        for line in jsonl.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let record: Bip329Record =
+                serde_json::from_str(line).map_err(LabelError::ParseRecord)?;
+            if record.kind != "tx" && record.kind != "output" {
+                continue;
+            }
+            conn.execute(
+                "INSERT INTO labels (ref_kind, ref_id, label) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(ref_kind, ref_id) DO UPDATE SET label = excluded.label",
+                (record.kind, record.reference, record.label),
+            )
+            .map_err(LabelError::Database)?;
+            count += 1;
+        }
+        Ok(count)
     }
 }
 
+/// BIP329 label-export record shape: `{"type": ..., "ref": ..., "label": ...}`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Bip329Record {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "ref")]
+    reference: String,
+    label: String,
+}
+
 pub struct Balance {
     pub immature: u64,
     pub trusted_pending: u64,
@@ -496,6 +1175,22 @@ pub struct Recipient {
     pub amount: u64,
 }
 
+pub struct OutPoint {
+    pub txid: Vec<u8>,
+    pub vout: u32,
+}
+
+impl TryFrom<OutPoint> for bitcoin::OutPoint {
+    type Error = bdk_wallet::bitcoin::hashes::FromSliceError;
+
+    fn try_from(outpoint: OutPoint) -> Result<Self, Self::Error> {
+        Ok(bitcoin::OutPoint {
+            txid: bitcoin::Txid::from_slice(&outpoint.txid)?,
+            vout: outpoint.vout,
+        })
+    }
+}
+
 pub struct BlockId {
     pub height: u32,
     pub hash: Vec<u8>,
@@ -510,6 +1205,7 @@ pub struct TxInfo {
     pub received: u64,
     /// How confirmed is this transaction?
     pub confirmations: u32,
+    pub label: Option<String>,
 }
 
 pub struct UtxoInfo {
@@ -520,6 +1216,7 @@ pub struct UtxoInfo {
     pub is_change: bool,
     pub derivation_index: u32,
     pub confirmations: u32,
+    pub label: Option<String>,
 }
 
 pub struct MempoolTx {
@@ -547,3 +1244,83 @@ impl ApplyResult {
         Self { relevant_txids }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt_entropy_roundtrip() {
+        let entropy = [7_u8; ENTROPY_LEN];
+        let blob = WalletHeader::encrypt_entropy(&entropy, "correct horse battery staple");
+        let decrypted =
+            WalletHeader::decrypt_entropy(&blob, "correct horse battery staple").unwrap();
+        assert_eq!(decrypted, entropy);
+    }
+
+    #[test]
+    fn decrypt_entropy_wrong_passphrase_fails() {
+        let entropy = [7_u8; ENTROPY_LEN];
+        let blob = WalletHeader::encrypt_entropy(&entropy, "correct horse battery staple");
+        let err = WalletHeader::decrypt_entropy(&blob, "wrong passphrase").unwrap_err();
+        assert!(matches!(err, LoadError::BadPassphrase));
+    }
+
+    /// Builds a synthetic checkpoint chain with the given heights, oldest first.
+    fn checkpoint_chain(heights: &[u32]) -> bdk_chain::CheckPoint {
+        let mut heights = heights.iter();
+        let mut cp = bdk_chain::CheckPoint::new(bdk_chain::BlockId {
+            height: *heights.next().unwrap(),
+            hash: BlockHash::all_zeros(),
+        });
+        for &height in heights {
+            cp = cp.insert(bdk_chain::BlockId {
+                height,
+                hash: BlockHash::from_byte_array([height as u8; 32]),
+            });
+        }
+        cp
+    }
+
+    #[test]
+    fn resolve_rollback_target_accepts_checkpoint_within_max_reorg() {
+        let tip = checkpoint_chain(&[0, 10, 20]);
+        let target = Wallet::resolve_rollback_target(&tip, 10).unwrap();
+        assert_eq!(target.height(), 10);
+    }
+
+    #[test]
+    fn resolve_rollback_target_rejects_height_without_exact_checkpoint() {
+        // No checkpoint at height 15: the nearest one at-or-below is 10. Silently treating
+        // that as a depth-10 rollback is the bug this case guards against.
+        let tip = checkpoint_chain(&[0, 10, 20]);
+        let err = Wallet::resolve_rollback_target(&tip, 15).unwrap_err();
+        assert!(matches!(err, ApplyBlockError::NoCheckpointAtHeight(15)));
+    }
+
+    #[test]
+    fn resolve_rollback_target_rejects_reorg_deeper_than_max_reorg() {
+        let tip = checkpoint_chain(&[0, MAX_REORG + 1]);
+        let err = Wallet::resolve_rollback_target(&tip, 0).unwrap_err();
+        assert!(matches!(
+            err,
+            ApplyBlockError::ReorgTooDeep { depth, max } if depth == MAX_REORG + 1 && max == MAX_REORG
+        ));
+    }
+
+    const TEST_TPRV: &str = "tprv8ZgxMBicQKsPcx5nBGsR63Pe8KnRUqmbJNENAfGftF3yuXoMMfTzWtymwcxr9jStZeNXWQ6yqybbA3yb9LBxnwx3JJj4OgfDmVNmH1Ymvp4";
+    const TEST_TPUB: &str = "tpubD6NzVbkrYhZ4WLczPJWReQycCJdd6YVWXubbVUFnJ5KgU5MDQrD998ZJLSmaB7GVcCnJSDWprxmrGkJ6SvgQC6QAXNxo1BUbQoGBm1bR1Wt";
+
+    #[test]
+    fn ensure_no_private_keys_rejects_descriptor_with_xprv() {
+        let descriptor = format!("wpkh({}/84'/1'/0'/0/*)", TEST_TPRV);
+        let err = Wallet::ensure_no_private_keys(&descriptor).unwrap_err();
+        assert!(matches!(err, CreateNewError::DescriptorHasPrivateKeys));
+    }
+
+    #[test]
+    fn ensure_no_private_keys_accepts_public_only_descriptor() {
+        let descriptor = format!("wpkh({}/84'/1'/0'/0/*)", TEST_TPUB);
+        Wallet::ensure_no_private_keys(&descriptor).unwrap();
+    }
+}